@@ -1,6 +1,8 @@
 use crate::input::actions::Action;
+use bitflags::bitflags;
 use clap::ArgEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 use strum_macros::{EnumDiscriminants, EnumIter, EnumString, ToString};
@@ -12,6 +14,9 @@ pub fn client_id_to_colors(
     colors: Palette,
 ) -> Option<(PaletteColor, PaletteColor)> {
     // (primary color, secondary color)
+    if let Some(named) = colors.named_color(&format!("client_{}", client_id)) {
+        return Some((named, colors.black));
+    }
     match client_id {
         1 => Some((colors.magenta, colors.black)),
         2 => Some((colors.blue, colors.black)),
@@ -28,16 +33,52 @@ pub fn client_id_to_colors(
 }
 
 pub fn single_client_color(colors: Palette) -> (PaletteColor, PaletteColor) {
-    (colors.green, colors.black)
+    match colors.named_color("single_client") {
+        Some(named) => (named, colors.black),
+        None => (colors.green, colors.black),
+    }
+}
+
+bitflags! {
+    /// The keyboard modifiers held down alongside a [`Key`]'s base keysym.
+    #[derive(Serialize, Deserialize)]
+    pub struct KeyModifiers: u8 {
+        const SHIFT = 0b0001;
+        const CTRL  = 0b0010;
+        const ALT   = 0b0100;
+        const SUPER = 0b1000;
+    }
+}
+
+impl Default for KeyModifiers {
+    fn default() -> Self {
+        KeyModifiers::empty()
+    }
 }
 
-// TODO: Add a shortened string representation (beyond `Display::fmt` below) that can be used when
-// screen space is scarce. Useful for e.g. "ENTER", "SPACE", "TAB" to display as Unicode
-// representations instead.
+impl fmt::Display for KeyModifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.contains(KeyModifiers::CTRL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        if self.contains(KeyModifiers::SUPER) {
+            write!(f, "Super+")?;
+        }
+        Ok(())
+    }
+}
+
+/// The base keysym of a [`Key`], independent of any modifiers held down alongside it.
 // NOTE: Do not reorder the key variants since that influences what the `status_bar` plugin
 // displays!
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
-pub enum Key {
+pub enum KeySym {
     PageDown,
     PageUp,
     Left,
@@ -51,59 +92,189 @@ pub enum Key {
     Insert,
     F(u8),
     Char(char),
-    Alt(CharOrArrow),
-    Ctrl(char),
-    BackTab,
+    Tab,
     Null,
     Esc,
 }
 
-impl fmt::Display for Key {
+impl fmt::Display for KeySym {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Key::Backspace => write!(f, "BACKSPACE"),
-            Key::Left => write!(f, "{}", Direction::Left),
-            Key::Right => write!(f, "{}", Direction::Right),
-            Key::Up => write!(f, "{}", Direction::Up),
-            Key::Down => write!(f, "{}", Direction::Down),
-            Key::Home => write!(f, "HOME"),
-            Key::End => write!(f, "END"),
-            Key::PageUp => write!(f, "PgUp"),
-            Key::PageDown => write!(f, "PgDn"),
-            Key::BackTab => write!(f, "TAB"),
-            Key::Delete => write!(f, "DEL"),
-            Key::Insert => write!(f, "INS"),
-            Key::F(n) => write!(f, "F{}", n),
-            Key::Char(c) => match c {
+            KeySym::Backspace => write!(f, "BACKSPACE"),
+            KeySym::Left => write!(f, "{}", Direction::Left),
+            KeySym::Right => write!(f, "{}", Direction::Right),
+            KeySym::Up => write!(f, "{}", Direction::Up),
+            KeySym::Down => write!(f, "{}", Direction::Down),
+            KeySym::Home => write!(f, "HOME"),
+            KeySym::End => write!(f, "END"),
+            KeySym::PageUp => write!(f, "PgUp"),
+            KeySym::PageDown => write!(f, "PgDn"),
+            KeySym::Tab => write!(f, "TAB"),
+            KeySym::Delete => write!(f, "DEL"),
+            KeySym::Insert => write!(f, "INS"),
+            KeySym::F(n) => write!(f, "F{}", n),
+            KeySym::Char(c) => match c {
                 '\n' => write!(f, "ENTER"),
                 '\t' => write!(f, "TAB"),
                 ' ' => write!(f, "SPACE"),
                 _ => write!(f, "{}", c),
             },
-            Key::Alt(c) => write!(f, "Alt+{}", c),
-            Key::Ctrl(c) => write!(f, "Ctrl+{}", Key::Char(*c)),
-            Key::Null => write!(f, "NULL"),
-            Key::Esc => write!(f, "ESC"),
+            KeySym::Null => write!(f, "NULL"),
+            KeySym::Esc => write!(f, "ESC"),
         }
     }
 }
 
+/// A keypress: a base [`KeySym`] paired with the [`KeyModifiers`] held down alongside it.
+///
+/// `BackTab` no longer exists as its own keysym — it's `Key { key: KeySym::Tab, modifiers:
+/// KeyModifiers::SHIFT }` — and `Ctrl`/`Alt` are no longer separate `Key` variants, just
+/// `KeyModifiers::CTRL`/`KeyModifiers::ALT` on whichever base keysym was pressed.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct Key {
+    pub key: KeySym,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(key: KeySym) -> Self {
+        Key {
+            key,
+            modifiers: KeyModifiers::empty(),
+        }
+    }
+
+    pub fn with_modifiers(key: KeySym, modifiers: KeyModifiers) -> Self {
+        Key { key, modifiers }
+    }
+
+    pub fn ctrl(c: char) -> Self {
+        Key::with_modifiers(KeySym::Char(c), KeyModifiers::CTRL)
+    }
+
+    pub fn alt(key: KeySym) -> Self {
+        Key::with_modifiers(key, KeyModifiers::ALT)
+    }
+
+    /// A minimal-width rendering for constrained status-bar columns: modifiers stay as
+    /// `Ctrl+`/`Alt+`/etc, but keysyms that have a single-glyph Unicode form (ENTER, TAB, SPACE,
+    /// ESC, BACKSPACE, DELETE — arrows already render as glyphs in [`KeySym`]'s `Display`) use it
+    /// instead of their spelled-out name.
+    pub fn to_compact_string(&self) -> String {
+        format!("{}{}", self.modifiers, self.key.to_compact_string())
+    }
+}
+
+impl KeySym {
+    fn to_compact_string(&self) -> String {
+        match self {
+            KeySym::Char('\n') => "⏎".to_string(),
+            KeySym::Char('\t') | KeySym::Tab => "⇥".to_string(),
+            KeySym::Char(' ') => "␣".to_string(),
+            KeySym::Esc => "⎋".to_string(),
+            KeySym::Backspace => "⌫".to_string(),
+            KeySym::Delete => "⌦".to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.modifiers, self.key)
+    }
+}
+
+/// The pre-redesign on-the-wire shape of [`Key`], kept only so serialized configs written
+/// before the `KeyModifiers` bitflag redesign keep loading. Only used via
+/// [`deserialize_key_config_compat`] at the (self-describing, YAML) config-parsing boundary —
+/// `Key`'s own `Deserialize` impl is the plain derived one, since `Key` also flows over bincode
+/// IPC, and `#[serde(untagged)]` requires a self-describing format to pick a variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+enum LegacyKey {
+    PageDown,
+    PageUp,
+    Left,
+    Down,
+    Up,
+    Right,
+    Home,
+    End,
+    Backspace,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Alt(LegacyCharOrArrow),
+    Ctrl(char),
+    BackTab,
+    Null,
+    Esc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 #[serde(untagged)]
-pub enum CharOrArrow {
+enum LegacyCharOrArrow {
     Char(char),
     Direction(Direction),
 }
 
-impl fmt::Display for CharOrArrow {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            CharOrArrow::Char(c) => write!(f, "{}", Key::Char(*c)),
-            CharOrArrow::Direction(d) => write!(f, "{}", d),
+impl From<LegacyKey> for Key {
+    fn from(legacy: LegacyKey) -> Self {
+        match legacy {
+            LegacyKey::PageDown => Key::new(KeySym::PageDown),
+            LegacyKey::PageUp => Key::new(KeySym::PageUp),
+            LegacyKey::Left => Key::new(KeySym::Left),
+            LegacyKey::Down => Key::new(KeySym::Down),
+            LegacyKey::Up => Key::new(KeySym::Up),
+            LegacyKey::Right => Key::new(KeySym::Right),
+            LegacyKey::Home => Key::new(KeySym::Home),
+            LegacyKey::End => Key::new(KeySym::End),
+            LegacyKey::Backspace => Key::new(KeySym::Backspace),
+            LegacyKey::Delete => Key::new(KeySym::Delete),
+            LegacyKey::Insert => Key::new(KeySym::Insert),
+            LegacyKey::F(n) => Key::new(KeySym::F(n)),
+            LegacyKey::Char(c) => Key::new(KeySym::Char(c)),
+            LegacyKey::Ctrl(c) => Key::ctrl(c),
+            LegacyKey::BackTab => Key::with_modifiers(KeySym::Tab, KeyModifiers::SHIFT),
+            LegacyKey::Null => Key::new(KeySym::Null),
+            LegacyKey::Esc => Key::new(KeySym::Esc),
+            LegacyKey::Alt(LegacyCharOrArrow::Char(c)) => Key::alt(KeySym::Char(c)),
+            LegacyKey::Alt(LegacyCharOrArrow::Direction(Direction::Left)) => Key::alt(KeySym::Left),
+            LegacyKey::Alt(LegacyCharOrArrow::Direction(Direction::Right)) => {
+                Key::alt(KeySym::Right)
+            }
+            LegacyKey::Alt(LegacyCharOrArrow::Direction(Direction::Up)) => Key::alt(KeySym::Up),
+            LegacyKey::Alt(LegacyCharOrArrow::Direction(Direction::Down)) => Key::alt(KeySym::Down),
         }
     }
 }
 
+/// Deserializes a `Key`, falling back to the pre-redesign [`LegacyKey`] shape on failure.
+///
+/// Relies on `#[serde(untagged)]`, which only works against self-describing formats (YAML,
+/// JSON, ...) — use this via `#[serde(deserialize_with = "...")]` on keybind config fields,
+/// *not* for anything that travels over bincode (e.g. the client/server IPC and plugin
+/// protocol), where `Key`'s plain derived `Deserialize` impl must be used instead.
+pub fn deserialize_key_config_compat<'de, D>(deserializer: D) -> Result<Key, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum KeyOnWire {
+        Current {
+            key: KeySym,
+            modifiers: KeyModifiers,
+        },
+        Legacy(LegacyKey),
+    }
+    match KeyOnWire::deserialize(deserializer)? {
+        KeyOnWire::Current { key, modifiers } => Ok(Key { key, modifiers }),
+        KeyOnWire::Legacy(legacy) => Ok(legacy.into()),
+    }
+}
+
 /// The four directions (left, right, up, down).
 #[derive(Eq, Clone, Copy, Debug, PartialEq, Hash, Deserialize, Serialize, PartialOrd, Ord)]
 pub enum Direction {
@@ -124,17 +295,82 @@ impl fmt::Display for Direction {
     }
 }
 
+/// A cursor position reported alongside a [`Mouse`] event, in terminal rows/columns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+    pub line: isize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: isize, column: usize) -> Self {
+        Position { line, column }
+    }
+}
+
+/// The mouse button identified in a [`Mouse::Press`], [`Mouse::Release`] or [`Mouse::Drag`]
+/// event. Wheel events are reported via [`Mouse::Scroll`]'s signed delta instead, so they have
+/// no variant here.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Bumped whenever `Mouse`'s on-wire shape changes incompatibly, as with the
+/// `Press`/`Release`/`Drag`/`Scroll` redesign that brought this constant in (shape 1 was the
+/// old `ScrollUp`/`ScrollDown`/`LeftClick`/`RightClick`/`Hold`/`Release` layout). Unlike `Key`,
+/// `Mouse` travels only over bincode IPC, which isn't self-describing, so there's no way to
+/// sniff an incoming payload's shape and decode it either way — the client/server handshake
+/// must exchange this version and refuse the connection on a mismatch instead of attempting to
+/// decode `Event::Mouse` bytes written by the other shape.
+pub const MOUSE_EVENT_SCHEMA_VERSION: u32 = 2;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-// FIXME: This should be extended to handle different button clicks (not just
-// left click) and the `ScrollUp` and `ScrollDown` events could probably be
-// merged into a single `Scroll(isize)` event.
 pub enum Mouse {
-    ScrollUp(usize),          // number of lines
-    ScrollDown(usize),        // number of lines
-    LeftClick(isize, usize),  // line and column
-    RightClick(isize, usize), // line and column
-    Hold(isize, usize),       // line and column
-    Release(isize, usize),    // line and column
+    Press(MouseButton, Position, KeyModifiers),
+    Release(MouseButton, Position, KeyModifiers),
+    Drag(MouseButton, Position, KeyModifiers),
+    Scroll(isize, Position, KeyModifiers), // signed delta (lines) and cursor position
+}
+
+impl Mouse {
+    /// Convenience constructors for new code migrating off the pre-redesign variant names.
+    /// These only help callers constructing a `Mouse` value in this process — they can't
+    /// recover a peer's already-serialized bytes written against [`MOUSE_EVENT_SCHEMA_VERSION`]
+    /// 1, which must be handled by refusing the IPC handshake on a version mismatch instead.
+    #[deprecated(note = "match on `Mouse::Press(MouseButton::Left, ..)` instead")]
+    pub fn new_left_click(line: isize, column: usize) -> Self {
+        Mouse::Press(
+            MouseButton::Left,
+            Position::new(line, column),
+            KeyModifiers::default(),
+        )
+    }
+
+    #[deprecated(note = "match on `Mouse::Press(MouseButton::Right, ..)` instead")]
+    pub fn new_right_click(line: isize, column: usize) -> Self {
+        Mouse::Press(
+            MouseButton::Right,
+            Position::new(line, column),
+            KeyModifiers::default(),
+        )
+    }
+
+    #[deprecated(note = "match on `Mouse::Scroll(delta, ..)` instead")]
+    pub fn new_scroll_up(lines: usize) -> Self {
+        Mouse::Scroll(lines as isize, Position::new(0, 0), KeyModifiers::default())
+    }
+
+    #[deprecated(note = "match on `Mouse::Scroll(delta, ..)` instead")]
+    pub fn new_scroll_down(lines: usize) -> Self {
+        Mouse::Scroll(
+            -(lines as isize),
+            Position::new(0, 0),
+            KeyModifiers::default(),
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, EnumDiscriminants, ToString, Serialize, Deserialize)]
@@ -151,6 +387,17 @@ pub enum Event {
     SystemClipboardFailure,
     InputReceived,
     Visible(bool),
+    PaneUpdate(Vec<PaneInfo>),
+    SessionUpdate(Vec<SessionInfo>),
+    PermissionRequest(Vec<PermissionType>),
+}
+
+/// A capability a plugin can ask the user to grant it, reported via
+/// [`Event::PermissionRequest`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, ToString, Serialize, Deserialize)]
+pub enum PermissionType {
+    ReadApplicationState,
+    ChangeApplicationState,
 }
 
 /// Describes the different input modes, which change the way that keystrokes will be interpreted.
@@ -254,17 +501,21 @@ impl FromStr for InputMode {
     }
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PaletteSource {
     Default,
     Xresources,
+    /// A named base16/base24-style theme, loaded from a theme definition file.
+    Theme(String),
 }
 impl Default for PaletteSource {
     fn default() -> PaletteSource {
         PaletteSource::Default
     }
 }
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+
+// NOTE: no longer `Copy`/`Hash` since `PaletteSource::Theme` and `extra` carry heap data.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Palette {
     pub source: PaletteSource,
     pub theme_hue: ThemeHue,
@@ -285,9 +536,61 @@ pub struct Palette {
     pub silver: PaletteColor,
     pub pink: PaletteColor,
     pub brown: PaletteColor,
+    /// Additional named color slots from a loaded theme, keyed by the name used to reference
+    /// them in layouts and plugin styling (not covered by the 17 fixed fields above).
+    pub extra: HashMap<String, PaletteColor>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+impl Palette {
+    /// Builds a [`Palette`] from a named theme's color map, recording the theme's name as its
+    /// [`PaletteSource`].
+    pub fn from_theme(name: impl Into<String>, extra: HashMap<String, PaletteColor>) -> Self {
+        Palette {
+            source: PaletteSource::Theme(name.into()),
+            extra,
+            ..Default::default()
+        }
+    }
+
+    /// Parses a base16/base24-style theme definition (one `name = RRGGBB`/`name = #RRGGBB`
+    /// entry per line; blank lines and `#`-prefixed comments are ignored) into a [`Palette`],
+    /// recording `theme_name` as its [`PaletteSource`]. Unparsable entries are skipped rather
+    /// than failing the whole theme, since a single bad line in a user's theme file shouldn't
+    /// keep the rest of it from loading.
+    pub fn parse_theme(theme_name: impl Into<String>, definition: &str) -> Self {
+        let mut extra = HashMap::new();
+        for line in definition.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=');
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                let name = name.trim();
+                let value = value.trim().trim_start_matches('#');
+                if value.len() != 6 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                    continue;
+                }
+                if let Ok(rgb) = u32::from_str_radix(value, 16) {
+                    let color = PaletteColor::Rgb((
+                        ((rgb >> 16) & 0xff) as u8,
+                        ((rgb >> 8) & 0xff) as u8,
+                        (rgb & 0xff) as u8,
+                    ));
+                    extra.insert(name.to_string(), color);
+                }
+            }
+        }
+        Palette::from_theme(theme_name, extra)
+    }
+
+    /// Looks up a color by name in the theme's extra color slots.
+    pub fn named_color(&self, name: &str) -> Option<PaletteColor> {
+        self.extra.get(name).copied()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Style {
     pub colors: Palette,
     pub rounded_corners: bool,
@@ -336,6 +639,26 @@ pub struct TabInfo {
     pub other_focused_clients: Vec<ClientId>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct PaneInfo {
+    /* subset of fields to publish to plugins */
+    pub id: u32,
+    pub title: String,
+    pub is_focused: bool,
+    pub is_fullscreen: bool,
+    pub is_floating: bool,
+    pub other_focused_clients: Vec<ClientId>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SessionInfo {
+    /* subset of fields to publish to plugins */
+    pub name: String,
+    pub tabs: Vec<TabInfo>,
+    pub connected_clients: usize,
+    pub is_current_session: bool,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct PluginIds {
     pub plugin_id: u32,